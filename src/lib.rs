@@ -14,10 +14,19 @@
 //!     .plugins(Plugins::new().plugin(
 //!         perseus_tailwind::get_tailwind_plugin,
 //!         perseus_tailwind::TailwindOptions {
-//!             in_file: "src/tailwind.css".into(),
-//!             // Don't put this in /static, it will trigger build loops.
-//!             // Put this in /dist or a custom folder and use a static alias instead.
-//!             out_file: "dist/tailwind.css".into(),
+//!             profiles: vec![perseus_tailwind::TailwindProfile {
+//!                 in_file: "src/tailwind.css".into(),
+//!                 // Don't put this in /static, it will trigger build loops.
+//!                 // Put this in /dist or a custom folder and use a static alias instead.
+//!                 out_file: "dist/tailwind.css".into(),
+//!                 config: None,
+//!                 cwd: None,
+//!             }],
+//!             version: None,
+//!             download_url: None,
+//!             binary_path: None,
+//!             expected_sha256: None,
+//!             watch: false,
 //!         },
 //!     ))
 //!     .static_alias("/tailwind.css", "dist/tailwind.css")
@@ -26,48 +35,142 @@
 //!
 //! If you're already using plugins just add the plugin to your `Plugins` as usual.
 //!
+//! # Multiple profiles
+//!
+//! `TailwindOptions::profiles` takes a `Vec`, so an app that needs more than one independent CSS
+//! bundle (e.g. a main stylesheet plus an admin panel or email template stylesheet) can just add
+//! another `TailwindProfile`. Each profile has its own `in_file`/`out_file` pair and optionally its
+//! own `config` file and working directory (`cwd`), and the CLI is invoked once per profile on
+//! every build. If a profile's build fails, the error names the profile's `in_file` so it's clear
+//! which one broke.
+//!
 //! # Using a custom binary
 //!
 //! If you for some reason want to use a specific version of the CLI or some other CLI with the same
 //! command line interface entirely, just place the binary with its default system-specific name
-//! (i.e. `tailwindcss-linux-arm64`) in the project directory.
+//! (i.e. `tailwindcss-linux-arm64`) in the project directory, or set `binary_path` to point at it
+//! directly. `version` and `download_url` let you pin or redirect the download instead, for
+//! reproducible builds or CI behind a proxy. `expected_sha256` can additionally pin the digest
+//! of the downloaded binary per platform, so a truncated download or a compromised mirror fails
+//! the build instead of silently installing a broken binary.
+//!
+//! # Watch mode
+//!
+//! Set `TailwindOptions::watch` to additionally run the CLI with `--watch` in the background
+//! after the normal one-shot build, so output CSS keeps rebuilding as source files change instead
+//! of going stale after the first build. `--minify` is never passed to the watcher. The watch
+//! processes are spawned once, the first time `before_build` runs, and are killed when the serve
+//! process receives Ctrl+C.
+//!
+//! **Only set `watch` for `perseus serve`.** This plugin has no way to distinguish `perseus
+//! serve` from `perseus build`/`perseus deploy` (both just call `before_build`), so setting
+//! `watch` for a one-shot build leaves an orphaned background CLI process running after the
+//! build finishes.
 //!
 //! # Stability
 //!
 //! The plugin is fairly simple and shouldn't break anything since it just executes the Tailwind CLI.
-//! The download and installation should work on Linux, MacOS and Windows on all architectures that
-//! are supported by Tailwind, but is currently only tested on Windows x64.
+//! The release asset name for the running platform is resolved at runtime, covering every
+//! target Tailwind ships a binary for (Linux arm64/armv7/x64, MacOS arm64/x64, Windows x64/arm64),
+//! but installation is currently only tested on Windows x64.
 
 #[cfg(engine)]
 use perseus::plugins::PluginAction;
 use perseus::plugins::{empty_control_actions_registrar, Plugin, PluginEnv};
 #[cfg(engine)]
-use std::{fs::File, io::Write, path::PathBuf, process::Command};
+use sha2::{Digest, Sha256};
+#[cfg(engine)]
+use std::{
+    fs::File,
+    io::Write,
+    path::Path,
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 static PLUGIN_NAME: &str = "tailwind-plugin";
 
-#[cfg(all(target_os = "linux", target_arch = "aarch64"))]
-static BINARY_NAME: &str = "tailwindcss-linux-arm64";
-#[cfg(all(target_os = "linux", target_arch = "arm"))]
-static BINARY_NAME: &str = "tailwindcss-linux-armv7";
-#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
-static BINARY_NAME: &str = "tailwindcss-linux-x64";
-#[cfg(all(target_os = "macos", target_arch = "aarch64"))]
-static BINARY_NAME: &str = "tailwindcss-macos-arm64";
-#[cfg(all(target_os = "macos", target_arch = "x86_64"))]
-static BINARY_NAME: &str = "tailwindcss-macos-x64";
-#[cfg(all(target_os = "windows", target_arch = "x86_64"))]
-static BINARY_NAME: &str = "tailwindcss-windows-x64.exe";
+/// Maps the running platform to the name of the Tailwind CLI release asset for it, e.g.
+/// `tailwindcss-linux-x64`. This is resolved at runtime rather than via `cfg!` so that building
+/// on a platform Tailwind doesn't ship a static for doesn't fail to compile, and so the
+/// `download_url`/`version` options can construct the right asset name for every platform without
+/// a recompile.
+#[cfg(engine)]
+fn binary_name() -> Result<&'static str, String> {
+    use std::env::consts::{ARCH, OS};
+    let name = match (OS, ARCH) {
+        ("linux", "aarch64") => "tailwindcss-linux-arm64",
+        ("linux", "arm") => "tailwindcss-linux-armv7",
+        ("linux", "x86_64") => "tailwindcss-linux-x64",
+        ("macos", "aarch64") => "tailwindcss-macos-arm64",
+        ("macos", "x86_64") => "tailwindcss-macos-x64",
+        ("windows", "x86_64") => "tailwindcss-windows-x64.exe",
+        ("windows", "aarch64") => "tailwindcss-windows-arm64.exe",
+        (os, arch) => {
+            return Err(format!(
+                "Unsupported platform for the Tailwind CLI: {os}/{arch}"
+            ))
+        }
+    };
+    Ok(name)
+}
 
 /// Options for the Tailwind CLI
 #[derive(Debug)]
 pub struct TailwindOptions {
+    /// The build profiles to run the Tailwind CLI for. Each profile produces its own CSS output
+    /// file and is invoked as a separate CLI run, so an app can emit several independent CSS
+    /// bundles without custom plugin code.
+    pub profiles: Vec<TailwindProfile>,
+    /// Pin the CLI to a specific released version (e.g. `"3.4.1"`) instead of always installing
+    /// `releases/latest`. If `download_url` is also set, `version` is substituted into its
+    /// `{version}` placeholder instead of being used to build the default GitHub releases URL.
+    /// Ignored if `binary_path` is set.
+    pub version: Option<String>,
+    /// A custom download URL template used instead of the GitHub releases URL, e.g. to point at
+    /// a fork or a bundled build. `{version}` is replaced with `version` (or `latest` if unset)
+    /// and `{target}` is replaced with the platform-specific binary name (e.g.
+    /// `tailwindcss-linux-x64`).
+    pub download_url: Option<String>,
+    /// Path to an already-installed Tailwind CLI binary. When set, the plugin skips downloading
+    /// entirely and runs this binary exactly as given (e.g. a bare `"tailwindcss"` resolves via
+    /// `$PATH`, same as any other value [`std::process::Command::new`] accepts), which covers
+    /// both the Nix/packaging case and a CLI already on `$PATH`.
+    pub binary_path: Option<PathBuf>,
+    /// Expected SHA-256 digests of the downloaded binary, keyed by the platform-specific binary
+    /// name (e.g. `tailwindcss-linux-x64`), as a lowercase hex string. If the digest for the
+    /// current platform doesn't match after downloading, installation fails instead of writing
+    /// the binary to disk. If `None`, or if there's no entry for the current platform, the
+    /// download isn't verified.
+    pub expected_sha256: Option<HashMap<String, String>>,
+    /// After the one-shot build, additionally start the CLI with `--watch` in the background, for
+    /// use with `perseus serve`. `--minify` is never passed to the watch processes; the one-shot
+    /// build before them still passes it in release builds.
+    pub watch: bool,
+}
+
+/// A single Tailwind build target: an input/output CSS pair, optionally with its own config
+/// file and working directory.
+#[derive(Debug, Clone)]
+pub struct TailwindProfile {
     /// The path to the input CSS file
     pub in_file: String,
     /// The path to the CSS file output by the CLI.\
     /// **DO NOT PUT THIS IN `/static` UNLESS YOU LIKE BUILD LOOPS!**\
     /// Always put it somewhere in `/dist` use static aliases instead.\
     pub out_file: String,
+    /// An optional path to a Tailwind config file for this profile, passed to the CLI as
+    /// `--config`. If unset, the CLI falls back to `tailwind.config.js` in `cwd` (or the current
+    /// directory), which this plugin will initialize if it doesn't already exist.
+    pub config: Option<String>,
+    /// An optional working directory to run the CLI in for this profile. If unset, the CLI runs
+    /// in the current directory.
+    pub cwd: Option<PathBuf>,
 }
 
 /// The plugin constructor
@@ -84,6 +187,9 @@ pub fn get_tailwind_plugin() -> Plugin<TailwindOptions> {
                     .register_plugin(PLUGIN_NAME, |_, data| {
                         let options = data.downcast_ref::<TailwindOptions>().unwrap();
                         try_run_tailwind(options)?;
+                        if options.watch {
+                            start_watchers(options)?;
+                        }
                         Ok(())
                     });
                 actions
@@ -102,46 +208,157 @@ pub fn get_tailwind_plugin() -> Plugin<TailwindOptions> {
     )
 }
 
+/// Resolves the path to the Tailwind CLI binary to run, installing it first if needed.
+///
+/// If `binary_path` is set, it's used exactly as given (e.g. a bare `"tailwindcss"` resolves via
+/// `$PATH`, as does any other value the OS would accept for [`Command::new`]) and is never
+/// installed or rewritten. Otherwise the default binary name is resolved to an absolute path
+/// relative to the current directory, since profiles may run the CLI with a different
+/// `current_dir`.
+#[cfg(engine)]
+fn resolve_cli_path(options: &TailwindOptions) -> Result<PathBuf, String> {
+    if let Some(binary_path) = &options.binary_path {
+        return Ok(binary_path.clone());
+    }
+
+    let name = binary_name()?;
+    let default = PathBuf::from(name);
+    if !default.exists() {
+        install_tailwind_cli(options, name)?;
+    }
+    std::env::current_dir()
+        .map_err(|_| "Failed to resolve current directory".to_string())
+        .map(|dir| dir.join(default))
+}
+
 #[cfg(engine)]
 fn try_run_tailwind(options: &TailwindOptions) -> Result<(), String> {
-    let cli = PathBuf::from(BINARY_NAME);
-    if !cli.exists() {
-        install_tailwind_cli()?;
+    let cli = resolve_cli_path(options)?;
+
+    let mut errors = Vec::new();
+    for profile in &options.profiles {
+        if let Err(err) = run_profile(&cli, profile) {
+            errors.push(format!("profile `{}`: {err}", profile.in_file));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+#[cfg(engine)]
+fn ensure_profile_config(profile: &TailwindProfile) -> Result<(String, PathBuf), String> {
+    let config_path = profile
+        .config
+        .clone()
+        .unwrap_or_else(|| "tailwind.config.js".to_string());
+    let cwd = profile.cwd.clone().unwrap_or_else(|| PathBuf::from("."));
+    if !cwd.join(&config_path).exists() {
+        init_tailwind(&cwd.join(&config_path))?;
+    }
+    Ok((config_path, cwd))
+}
+
+/// Handles to the background `--watch` CLI processes started by [`start_watchers`], one per
+/// profile. They're left running for the lifetime of the dev server and killed by
+/// [`register_watch_cleanup`]'s Ctrl+C handler.
+#[cfg(engine)]
+static WATCHERS: Mutex<Vec<Child>> = Mutex::new(Vec::new());
+#[cfg(engine)]
+static WATCHERS_STARTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(engine)]
+fn start_watchers(options: &TailwindOptions) -> Result<(), String> {
+    if WATCHERS_STARTED.swap(true, Ordering::SeqCst) {
+        return Ok(());
     }
-    if !PathBuf::from("tailwind.config.js").exists() {
-        init_tailwind()?;
+
+    let cli = resolve_cli_path(options)?;
+
+    register_watch_cleanup();
+
+    let mut watchers = WATCHERS.lock().unwrap();
+    for profile in &options.profiles {
+        let (config_path, cwd) = ensure_profile_config(profile)?;
+        let mut args = vec!["-i", &profile.in_file, "-o", &profile.out_file, "--watch"];
+        if profile.config.is_some() {
+            args.push("--config");
+            args.push(&config_path);
+        }
+
+        log::info!(
+            "Starting Tailwind CLI in watch mode for `{}`...",
+            profile.in_file
+        );
+        let child = Command::new(&cli)
+            .args(args)
+            .current_dir(&cwd)
+            .spawn()
+            .map_err(|_| "Failed to run Tailwind CLI in watch mode")?;
+        watchers.push(child);
     }
 
-    let mut args = vec!["-i", &options.in_file, "-o", &options.out_file];
+    Ok(())
+}
+
+/// Installs a Ctrl+C handler that kills all tracked watch processes before the serve process
+/// exits, so they don't linger as orphans. A no-op on any call after the first.
+#[cfg(engine)]
+fn register_watch_cleanup() {
+    let _ = ctrlc::set_handler(|| {
+        if let Ok(mut watchers) = WATCHERS.lock() {
+            for child in watchers.iter_mut() {
+                let _ = child.kill();
+            }
+        }
+        std::process::exit(130);
+    });
+}
+
+#[cfg(engine)]
+fn run_profile(cli: &Path, profile: &TailwindProfile) -> Result<(), String> {
+    let (config_path, cwd) = ensure_profile_config(profile)?;
+
+    let mut args = vec!["-i", &profile.in_file, "-o", &profile.out_file];
+    if profile.config.is_some() {
+        args.push("--config");
+        args.push(&config_path);
+    }
     if cfg!(not(debug_assertions)) {
         args.push("--minify");
     }
 
-    let child = Command::new(format!("./{BINARY_NAME}"))
+    let child = Command::new(cli)
         .args(args)
+        .current_dir(&cwd)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
         .spawn()
         .map_err(|_| "Failed to run Tailwind CLI")?;
 
     let output = child
         .wait_with_output()
         .map_err(|_| "Failed to wait on Tailwind CLI")?;
-    let output = String::from_utf8_lossy(&output.stdout);
 
-    // Try to figure out if there was an error
-    if output.contains('{') {
-        return Err(output.to_string());
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "Tailwind CLI exited with {}: {}",
+            output.status, stderr
+        ));
     }
 
     Ok(())
 }
 
 #[cfg(engine)]
-fn install_tailwind_cli() -> Result<(), String> {
+fn install_tailwind_cli(options: &TailwindOptions, name: &str) -> Result<(), String> {
     log::info!("Tailwind CLI not found, installing...");
     log::info!("Downloading binary for this platform...");
-    let url = format!(
-        "https://github.com/tailwindlabs/tailwindcss/releases/latest/download/{BINARY_NAME}"
-    );
+    let url = resolve_download_url(options, name);
     let binary = tokio::task::block_in_place(move || {
         reqwest::blocking::get(url)
             .map_err(|_| {
@@ -151,8 +368,23 @@ fn install_tailwind_cli() -> Result<(), String> {
             .map_err(|_| "Failed to read binary content of the tailwind binary download")
     })?;
 
-    log::info!("Writing to disk as {BINARY_NAME}...");
-    let mut file = File::create(BINARY_NAME).map_err(|_| "Failed to create binary file")?;
+    if let Some(expected) = options
+        .expected_sha256
+        .as_ref()
+        .and_then(|digests| digests.get(name))
+    {
+        let mut hasher = Sha256::new();
+        hasher.update(&binary);
+        let actual = format!("{:x}", hasher.finalize());
+        if &actual != expected {
+            return Err(format!(
+                "SHA-256 mismatch for {name}: expected {expected}, got {actual}"
+            ));
+        }
+    }
+
+    log::info!("Writing to disk as {name}...");
+    let mut file = File::create(name).map_err(|_| "Failed to create binary file")?;
     file.write_all(&binary)
         .map_err(|_| "Failed to write binary to disk")?;
     #[cfg(target_family = "unix")]
@@ -173,13 +405,27 @@ fn install_tailwind_cli() -> Result<(), String> {
 }
 
 #[cfg(engine)]
-fn init_tailwind() -> Result<(), String> {
+fn resolve_download_url(options: &TailwindOptions, name: &str) -> String {
+    if let Some(template) = &options.download_url {
+        template
+            .replace("{version}", options.version.as_deref().unwrap_or("latest"))
+            .replace("{target}", name)
+    } else if let Some(version) = &options.version {
+        format!(
+            "https://github.com/tailwindlabs/tailwindcss/releases/download/v{version}/{name}"
+        )
+    } else {
+        format!("https://github.com/tailwindlabs/tailwindcss/releases/latest/download/{name}")
+    }
+}
+
+#[cfg(engine)]
+fn init_tailwind(config_path: &Path) -> Result<(), String> {
     log::info!(
         "Initializing Tailwind to search all Rust files in 'src' and all HTML files in 'static'."
     );
     let default_config = include_bytes!("default-config.js");
-    let mut config =
-        File::create("tailwind.config.js").map_err(|_| "Failed to create config file")?;
+    let mut config = File::create(config_path).map_err(|_| "Failed to create config file")?;
     config
         .write_all(default_config)
         .map_err(|_| "Failed to write default config")?;